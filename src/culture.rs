@@ -0,0 +1,63 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A bundled mapping from WiX culture codes to the `LCID`/codepage pair
+//! needed to set the `-dLanguage`/`-dCodepage` candle defines, which the
+//! `Package` element's `Languages`/`Codepage` attributes in the WXS template
+//! read.
+
+/// The default culture used when none is specified on the `Wix` builder.
+pub const DEFAULT_CULTURE: &str = "en-US";
+
+/// The WiX LCID and codepage for a single culture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Language {
+    pub lcid: u32,
+    pub codepage: u32,
+}
+
+/// A small, bundled table of commonly used cultures. This is not meant to be
+/// exhaustive; users targeting a culture that is missing here can still pass
+/// it through to `light`/`candle`, just without a matching `-dLanguage`
+/// define.
+const LANGUAGES: &[(&str, Language)] = &[
+    ("en-US", Language { lcid: 1033, codepage: 1252 }),
+    ("fr-FR", Language { lcid: 1036, codepage: 1252 }),
+    ("de-DE", Language { lcid: 1031, codepage: 1252 }),
+    ("ja-JP", Language { lcid: 1041, codepage: 932 }),
+];
+
+/// Looks up the `Language` for a culture code, e.g. `"fr-FR"`.
+pub fn language(culture: &str) -> Option<Language> {
+    LANGUAGES
+        .iter()
+        .find(|&&(code, _)| code.eq_ignore_ascii_case(culture))
+        .map(|&(_, lang)| lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_lookup_is_case_insensitive() {
+        assert_eq!(language("fr-fr"), language("FR-FR"));
+        assert!(language("fr-fr").is_some());
+    }
+
+    #[test]
+    fn language_lookup_rejects_unknown_culture() {
+        assert_eq!(language("xx-XX"), None);
+    }
+}
@@ -13,18 +13,28 @@
 // limitations under the License.
 
 #[macro_use] extern crate log;
-extern crate toml;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
+extern crate serde_json;
+extern crate uuid;
+
+mod culture;
+mod metadata;
+mod sign;
+mod wxs;
+
+pub use sign::{Certificate, DigestAlgorithm, SignConfig, Timestamp};
 
 use std::default::Default;
 use std::error::Error as StdError;
 use std::fmt;
-use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-use toml::Value;
+use std::process::Command;
+
+use culture::DEFAULT_CULTURE;
+use metadata::Metadata;
 
-const CARGO_MANIFEST_FILE: &str = "Cargo.toml";
 const WIX_TOOLSET_COMPILER: &str = "candle";
 const WIX_TOOLSET_LINKER: &str = "light";
 const SIGNTOOL: &str = "signtool";
@@ -38,24 +48,64 @@ pub fn print_template() -> Result<(), Error> {
     Ok(())
 }
 
+/// The captured failure of a subprocess (`cargo`, `candle`, `light`, or `signtool`), carrying
+/// enough context to explain the failure instead of the generic messages previously returned.
+#[derive(Debug)]
+pub struct CommandError {
+    /// The command that was run, e.g. `"candle"` or a hook's shell command text.
+    command: String,
+    /// The exit code of the command, if the process ran to completion.
+    code: Option<i32>,
+    /// The captured stderr output of the command, if any was captured.
+    stderr: String,
+}
+
+impl CommandError {
+    fn new(command: &str, code: Option<i32>, stderr: String) -> Self {
+        CommandError { command: command.to_owned(), code, stderr }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "'{}' failed with exit code {}", self.command, code)?,
+            None => write!(f, "'{}' failed", self.command)?,
+        }
+        if !self.stderr.trim().is_empty() {
+            write!(f, ": {}", self.stderr.trim())?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for CommandError {
+    fn description(&self) -> &str {
+        "CommandError"
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
-    /// A build operation for the release binary failed.
-    Build(String),
-    /// A compiler operation failed.
-    Compile(String),
+    /// Building the release binary failed.
+    Build(CommandError),
+    /// Compiling the installer with `candle` failed.
+    Compile(CommandError),
     /// A generic or custom error occurred. The message should contain the detailed information.
     Generic(String),
     /// An I/O operation failed.
     Io(io::Error),
-    /// A linker operation failed.
-    Link(String),
-    /// A needed field within the `Cargo.toml` manifest could not be found.
+    /// A `before_build`/`after_build` hook command failed.
+    Hook(CommandError),
+    /// Linking the installer with `light` failed.
+    Link(CommandError),
+    /// The package or binary to build could not be resolved from the
+    /// workspace's metadata.
     Manifest(String),
-    /// A signing operation failed.
-    Sign(String),
-    /// Parsing of the `Cargo.toml` manifest failed.
-    Toml(toml::de::Error),
+    /// Invoking or parsing the output of `cargo metadata` failed.
+    Metadata(String),
+    /// Signing the installer with `signtool` failed.
+    Sign(CommandError),
 }
 
 impl Error {
@@ -72,8 +122,9 @@ impl Error {
             Error::Io(..) => 4,
             Error::Link(..) => 5,
             Error::Manifest(..) => 6,
-            Error::Sign(..) => 7,
-            Error::Toml(..) => 8,
+            Error::Metadata(..) => 7,
+            Error::Sign(..) => 8,
+            Error::Hook(..) => 9,
         }
     }
 }
@@ -87,15 +138,20 @@ impl StdError for Error {
             Error::Io(..) => "Io",
             Error::Link(..) => "Link",
             Error::Manifest(..) => "Manifest",
+            Error::Metadata(..) => "Metadata",
             Error::Sign(..) => "Sign",
-            Error::Toml(..) => "TOML",
+            Error::Hook(..) => "Hook",
         }
     }
 
     fn cause(&self) -> Option<&StdError> {
         match *self {
             Error::Io(ref err) => Some(err),
-            Error::Toml(ref err) => Some(err),
+            Error::Build(ref err) => Some(err),
+            Error::Compile(ref err) => Some(err),
+            Error::Link(ref err) => Some(err),
+            Error::Sign(ref err) => Some(err),
+            Error::Hook(ref err) => Some(err),
             _ => None
         }
     }
@@ -104,14 +160,15 @@ impl StdError for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Error::Build(ref msg) => write!(f, "{}", msg),
-            Error::Compile(ref msg) => write!(f, "{}", msg),
+            Error::Build(ref err) => write!(f, "Failed to build the release executable: {}", err),
+            Error::Compile(ref err) => write!(f, "Failed to compile the installer: {}", err),
             Error::Generic(ref msg) => write!(f, "{}", msg),
             Error::Io(ref err) => write!(f, "{}", err),
-            Error::Link(ref msg) => write!(f, "{}", msg),
-            Error::Manifest(ref var) => write!(f, "No '{}' field found in the package's manifest (Cargo.toml)", var),
-            Error::Sign(ref msg) => write!(f, "{}", msg),
-            Error::Toml(ref err) => write!(f, "{}", err),
+            Error::Link(ref err) => write!(f, "Failed to link the installer: {}", err),
+            Error::Manifest(ref msg) => write!(f, "{}", msg),
+            Error::Metadata(ref msg) => write!(f, "{}", msg),
+            Error::Sign(ref err) => write!(f, "Failed to sign the installer: {}", err),
+            Error::Hook(ref err) => write!(f, "Hook failed: {}", err),
         }
     }
 }
@@ -122,16 +179,11 @@ impl From<io::Error> for Error {
     }
 }
 
-impl From<toml::de::Error> for Error {
-    fn from(err: toml::de::Error) -> Error {
-        Error::Toml(err)
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Platform {
     X86,
     X64,
+    Arm64,
 }
 
 impl Platform {
@@ -139,6 +191,20 @@ impl Platform {
         match *self {
             Platform::X86 => "i686",
             Platform::X64 => "x86_64",
+            Platform::Arm64 => "aarch64",
+        }
+    }
+
+    /// Maps a target triple's architecture (e.g. `"x86_64-pc-windows-msvc"`) to a `Platform`.
+    pub fn from_triple(triple: &str) -> Result<Self, Error> {
+        if triple.starts_with("x86_64-") {
+            Ok(Platform::X64)
+        } else if triple.starts_with("i686-") || triple.starts_with("i586-") {
+            Ok(Platform::X86)
+        } else if triple.starts_with("aarch64-") {
+            Ok(Platform::Arm64)
+        } else {
+            Err(Error::Generic(format!("Unsupported target triple '{}'", triple)))
         }
     }
 }
@@ -148,6 +214,7 @@ impl fmt::Display for Platform {
         match *self {
             Platform::X86 => write!(f, "x86"),
             Platform::X64 => write!(f, "x64"),
+            Platform::Arm64 => write!(f, "arm64"),
         }
     }
 }
@@ -162,9 +229,60 @@ impl Default for Platform {
     }
 }
 
+/// Builds a `Command` that runs `script` through the platform shell, so hook entries can be
+/// arbitrary shell command lines instead of a single executable with fixed arguments.
+fn shell_command(script: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(script);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        cmd
+    }
+}
+
+/// Runs each hook command in `crate_root`, in order, aborting on the first failure.
+fn run_hooks(hooks: &[String], crate_root: &Path, capture_output: bool) -> Result<(), Error> {
+    for hook in hooks {
+        info!("Running hook: {}", hook);
+        let mut cmd = shell_command(hook);
+        cmd.current_dir(crate_root);
+        execute(cmd, hook, capture_output).map_err(Error::Hook)?;
+    }
+    Ok(())
+}
+
+/// Runs `cmd`, capturing its stderr when `capture_output` is set so that a non-zero exit can be
+/// turned into a `CommandError` carrying the diagnostic text, instead of a silently dropped one.
+fn execute(mut cmd: Command, command: &str, capture_output: bool) -> Result<(), CommandError> {
+    if capture_output {
+        let output = cmd.output().map_err(|e| CommandError::new(command, None, e.to_string()))?;
+        if !output.status.success() {
+            return Err(CommandError::new(command, output.status.code(), String::from_utf8_lossy(&output.stderr).into_owned()));
+        }
+    } else {
+        let status = cmd.status().map_err(|e| CommandError::new(command, None, e.to_string()))?;
+        if !status.success() {
+            return Err(CommandError::new(command, status.code(), String::new()));
+        }
+    }
+    Ok(())
+}
+
 pub struct Wix {
     sign: bool,
     capture_output: bool,
+    package: Option<String>,
+    bin: Option<String>,
+    cultures: Vec<String>,
+    loc: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    sign_config: SignConfig,
+    target: Option<String>,
+    before_build: Vec<String>,
+    after_build: Vec<String>,
 }
 
 impl Wix {
@@ -172,6 +290,15 @@ impl Wix {
         Wix {
             sign: false,
             capture_output: true,
+            package: None,
+            bin: None,
+            cultures: vec![String::from(DEFAULT_CULTURE)],
+            loc: Vec::new(),
+            files: Vec::new(),
+            sign_config: SignConfig::default(),
+            target: None,
+            before_build: Vec::new(),
+            after_build: Vec::new(),
         }
     }
 
@@ -185,161 +312,207 @@ impl Wix {
         self
     }
 
+    /// Selects which workspace member to package when more than one package
+    /// is present.
+    pub fn package(mut self, p: &str) -> Self {
+        self.package = Some(p.to_owned());
+        self
+    }
+
+    /// Selects which `[[bin]]` target to package when a package has more
+    /// than one.
+    pub fn bin(mut self, b: &str) -> Self {
+        self.bin = Some(b.to_owned());
+        self
+    }
+
+    /// Sets the list of WiX cultures (e.g. `"en-US"`, `"fr-FR"`) to build. One
+    /// localized MSI is produced per culture. Defaults to `["en-US"]`.
+    pub fn cultures(mut self, cultures: Vec<String>) -> Self {
+        self.cultures = cultures;
+        self
+    }
+
+    /// Sets the `.wxl` localization files passed to `light` via `-loc`.
+    pub fn loc(mut self, loc: Vec<PathBuf>) -> Self {
+        self.loc = loc;
+        self
+    }
+
+    /// Sets additional files or directories to harvest into the installer
+    /// when no hand-written `wix/main.wxs` is present. See `run`.
+    pub fn files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Sets the `signtool` timestamp server, certificate, digest algorithm, and description used
+    /// when `sign` is enabled.
+    pub fn sign_config(mut self, sign_config: SignConfig) -> Self {
+        self.sign_config = sign_config;
+        self
+    }
+
+    /// Cross-compiles for a specific target triple (e.g. `"aarch64-pc-windows-msvc"`), passing
+    /// `--target` to `cargo build` and locating the binary under `target/<triple>/release/`.
+    pub fn target(mut self, triple: &str) -> Self {
+        self.target = Some(triple.to_owned());
+        self
+    }
+
+    /// Sets shell commands to run, in order, in the crate root before the build/compile/link
+    /// pipeline starts. Aborts the run if any command exits non-zero.
+    pub fn before_build(mut self, hooks: Vec<String>) -> Self {
+        self.before_build = hooks;
+        self
+    }
+
+    /// Sets shell commands to run, in order, in the crate root after the pipeline finishes (and
+    /// after signing, if enabled). Aborts the run if any command exits non-zero.
+    pub fn after_build(mut self, hooks: Vec<String>) -> Self {
+        self.after_build = hooks;
+        self
+    }
+
     /// Runs the subcommand to build the release binary, compile, link, and possibly sign the installer
-    /// (msi).
-    pub fn run(self) -> Result<(), Error> {
-        let cargo_file_path = Path::new(CARGO_MANIFEST_FILE);
-        debug!("cargo_file_path = {:?}", cargo_file_path);
-        let mut cargo_file = File::open(cargo_file_path)?;
-        let mut cargo_file_content = String::new();
-        cargo_file.read_to_string(&mut cargo_file_content)?;
-        let cargo_values = cargo_file_content.parse::<Value>()?;
-        let pkg_version = cargo_values
-            .get("package")
-            .and_then(|p| p.as_table())
-            .and_then(|t| t.get("version"))
-            .and_then(|v| v.as_str())
-            .ok_or(Error::Manifest(String::from("version")))?;
+    /// (msi). Returns the path to each produced MSI, one per requested culture.
+    pub fn run(self) -> Result<Vec<PathBuf>, Error> {
+        let metadata = Metadata::from_cargo()?;
+        let package = metadata.select_package(self.package.as_deref())?;
+        let bin = package.select_bin(self.bin.as_deref())?;
+        let pkg_version = &package.version;
         debug!("pkg_version = {:?}", pkg_version);
-        let pkg_name = cargo_values
-            .get("package")
-            .and_then(|p| p.as_table())
-            .and_then(|t| t.get("name"))
-            .and_then(|n| n.as_str())
-            .ok_or(Error::Manifest(String::from("name")))?;
+        let pkg_name = &package.name;
         debug!("pkg_name = {:?}", pkg_name);
-        let pkg_description = cargo_values
-            .get("package")
-            .and_then(|p| p.as_table())
-            .and_then(|t| t.get("description"))
-            .and_then(|d| d.as_str())
-            .ok_or(Error::Manifest(String::from("description")))?;
-        let pkg_author = cargo_values
-            .get("package")
-            .and_then(|p| p.as_table())
-            .and_then(|t| t.get("authors"))
-            .and_then(|a| a.as_array())
-            .and_then(|a| a.get(0)) // For now, just use the first author
-            .and_then(|f| f.as_str())
-            .ok_or(Error::Manifest(String::from("authors")))?;
+        let pkg_description = package.description.as_deref().unwrap_or("");
         debug!("pkg_description = {:?}", pkg_description);
-        let bin_name = cargo_values
-            .get("bin")
-            .and_then(|b| b.as_table())
-            .and_then(|t| t.get("name"))
-            .and_then(|n| n.as_str())
-            .unwrap_or(pkg_name);
+        let pkg_author = package.authors.first().map(|a| a.as_str()).unwrap_or(""); // For now, just use the first author
+        let bin_name = &bin.name;
         debug!("bin_name = {:?}", bin_name);
-        let platform = if cfg!(target_arch = "x86_64") {
-            Platform::X64
-        } else {
-            Platform::X86
+        let crate_root = package.manifest_path.parent()
+            .ok_or_else(|| Error::Metadata(String::from("Could not determine the crate root from the manifest path")))?;
+        let platform = match self.target {
+            Some(ref triple) => Platform::from_triple(triple)?,
+            None => Platform::default(),
         };
         debug!("platform = {:?}", platform);
-        let mut main_wxs = PathBuf::from("wix");
+        let mut main_wxs = crate_root.to_path_buf();
+        main_wxs.push("wix");
         main_wxs.push("main");
         main_wxs.set_extension("wxs");
         debug!("main_wxs = {:?}", main_wxs);
-        let mut main_wixobj = PathBuf::from("target");
-        main_wixobj.push("wix");
-        main_wixobj.push("build");
-        main_wixobj.push("main");
-        main_wixobj.set_extension("wixobj");
-        debug!("main_wixobj = {:?}", main_wixobj);
-        let mut main_msi = PathBuf::from("target");
-        main_msi.push("wix");
-        // Do NOT use the `set_extension` method for the MSI path. Since the pkg_version is in X.X.X
-        // format, the `set_extension` method will replace the Patch version number and
-        // architecture/platform with `msi`.  Instead, just include the extension in the formatted
-        // name.
-        main_msi.push(&format!("{}-{}-{}.msi", pkg_name, pkg_version, platform.arch()));
-        debug!("main_msi = {:?}", main_msi);
+        // Run the before-build hooks before the pipeline starts so generated resources are in place
+        // before `cargo build` and the WXS harvesting/compile/link steps run.
+        run_hooks(&self.before_build, crate_root, self.capture_output)?;
         // Build the binary with the release profile. If a release binary has already been built, then
         // this will essentially do nothing.
         info!("Building release binary");
-        if let Some(status) = {
+        {
             let mut builder = Command::new("cargo");
-            if self.capture_output {
-                builder.stdout(Stdio::null());
-                builder.stderr(Stdio::null());
-            }
-            builder.arg("build")
-                .arg("--release")
-                .status()
-        }.ok() {
-            if !status.success() {
-                // TODO: Add better error message
-                return Err(Error::Build(String::from("Failed to build the release executable")));
+            builder.arg("build").arg("--release");
+            if let Some(ref triple) = self.target {
+                builder.arg("--target").arg(triple);
             }
+            execute(builder, "cargo build", self.capture_output).map_err(Error::Build)?;
         }
-        // Compile the installer
-        info!("Compiling installer");
-        if let Some(status) = {
-            let mut compiler = Command::new(WIX_TOOLSET_COMPILER);
-            if self.capture_output {
-                compiler.stdout(Stdio::null());
-                compiler.stderr(Stdio::null());
-            } 
-            compiler.arg(format!("-dVersion={}", pkg_version))
-                .arg(format!("-dPlatform={}", platform))
-                .arg(format!("-dProductName={}", pkg_name))
-                .arg(format!("-dBinaryName={}", bin_name))
-                .arg(format!("-dDescription={}", pkg_description))
-                .arg(format!("-dAuthor={}", pkg_author))
-                .arg("-o")
-                .arg(&main_wixobj)
-                .arg(&main_wxs)
-                .status()
-        }.ok() {
-            if !status.success() {
-                // TODO: Add better error message
-                return Err(Error::Compile(String::from("Failed to compile the installer")));
+        // Generate a `main.wxs` by harvesting the built binary and any extra files, unless the
+        // project already has a hand-written one.
+        if !main_wxs.exists() {
+            info!("No wix/main.wxs found; generating one from the built binary and extra files");
+            let mut bin_path = metadata.target_directory.clone();
+            if let Some(ref triple) = self.target {
+                bin_path.push(triple);
             }
+            bin_path.push("release");
+            bin_path.push(bin_name);
+            bin_path.set_extension("exe");
+            let mut harvested = vec![wxs::HarvestedFile {
+                source: bin_path,
+                install_name: format!("{}.exe", bin_name),
+            }];
+            harvested.extend(wxs::harvest(&self.files)?);
+            let manifest = wxs::Manifest {
+                product_name: pkg_name.as_str(),
+                version: pkg_version.as_str(),
+                manufacturer: pkg_author,
+                upgrade_code: wxs::upgrade_code(pkg_name),
+            };
+            wxs::write_if_missing(&main_wxs, &manifest, &harvested)?;
         }
-        // Link the installer
-        info!("Linking the installer");
-        if let Some(status) = {
-            let mut linker = Command::new(WIX_TOOLSET_LINKER);
-            if self.capture_output {    
-                linker.stdout(Stdio::null());
-                linker.stderr(Stdio::null());
+        // One MSI is produced per requested culture, re-compiling and re-linking so the
+        // `-dLanguage`/`-dCodepage` defines match the culture being linked, for a `main.wxs` whose
+        // `Package` element reads them. The generated `main.wxs` does not read these defines and
+        // always declares `Language="1033"`.
+        let mut msis = Vec::with_capacity(self.cultures.len());
+        for culture in &self.cultures {
+            let culture_slug = culture.to_lowercase();
+            let mut main_wixobj = metadata.target_directory.clone();
+            main_wixobj.push("wix");
+            main_wixobj.push("build");
+            main_wixobj.push(format!("main-{}", culture_slug));
+            main_wixobj.set_extension("wixobj");
+            debug!("main_wixobj = {:?}", main_wixobj);
+            let mut main_msi = metadata.target_directory.clone();
+            main_msi.push("wix");
+            // Do NOT use the `set_extension` method for the MSI path. Since the pkg_version is in X.X.X
+            // format, the `set_extension` method will replace the Patch version number and
+            // architecture/platform with `msi`.  Instead, just include the extension in the formatted
+            // name.
+            if self.cultures.len() == 1 && culture == DEFAULT_CULTURE {
+                main_msi.push(format!("{}-{}-{}.msi", pkg_name, pkg_version, platform.arch()));
+            } else {
+                main_msi.push(format!("{}-{}-{}-{}.msi", pkg_name, pkg_version, platform.arch(), culture_slug));
+            }
+            debug!("main_msi = {:?}", main_msi);
+            let language = ::culture::language(culture);
+            // Compile the installer
+            info!("Compiling installer for culture '{}'", culture);
+            {
+                let mut compiler = Command::new(WIX_TOOLSET_COMPILER);
+                compiler.arg(format!("-dVersion={}", pkg_version))
+                    .arg(format!("-dPlatform={}", platform))
+                    .arg(format!("-dProductName={}", pkg_name))
+                    .arg(format!("-dBinaryName={}", bin_name))
+                    .arg(format!("-dDescription={}", pkg_description))
+                    .arg(format!("-dAuthor={}", pkg_author));
+                if let Some(language) = language {
+                    compiler.arg(format!("-dLanguage={}", language.lcid))
+                        .arg(format!("-dCodepage={}", language.codepage));
+                }
+                compiler.arg("-o")
+                    .arg(&main_wixobj)
+                    .arg(&main_wxs);
+                execute(compiler, WIX_TOOLSET_COMPILER, self.capture_output).map_err(Error::Compile)?;
             }
-            linker.arg("-ext")
-                .arg("WixUIExtension")
-                .arg("-cultures:en-us")
-                .arg(&main_wixobj)
-                .arg("-out")
-                .arg(&main_msi)
-                .status()
-        }.ok() {
-            if !status.success() {
-                // TODO: Add better error message
-                return Err(Error::Link(String::from("Failed to link the installer")));
+            // Link the installer
+            info!("Linking the installer for culture '{}'", culture);
+            {
+                let mut linker = Command::new(WIX_TOOLSET_LINKER);
+                linker.arg("-ext")
+                    .arg("WixUIExtension")
+                    .arg(format!("-cultures:{}", culture));
+                for loc in &self.loc {
+                    linker.arg("-loc").arg(loc);
+                }
+                linker.arg(&main_wixobj)
+                    .arg("-out")
+                    .arg(&main_msi);
+                execute(linker, WIX_TOOLSET_LINKER, self.capture_output).map_err(Error::Link)?;
             }
+            msis.push(main_msi);
         }
         // Sign the installer
         if self.sign {
-            info!("Signing the installer");
-            if let Some(status) = {
+            for main_msi in &msis {
+                info!("Signing the installer");
                 let mut signer = Command::new(SIGNTOOL);
-                if self.capture_output {
-                    signer.stdout(Stdio::null());
-                    signer.stderr(Stdio::null());
-                }
-                signer.arg("sign")
-                    .arg("/a")
-                    //.arg("/t")
-                    //.arg("http://timestamp.comodoca.com")
-                    .arg(&main_msi)
-                    .status()
-            }.ok() {
-                if !status.success() {
-                    // TODO: Add better error message
-                    return Err(Error::Sign(String::from("Failed to sign the installer")));
-                }
+                self.sign_config.apply(&mut signer);
+                signer.arg(main_msi);
+                execute(signer, SIGNTOOL, self.capture_output).map_err(Error::Sign)?;
             }
         }
-        Ok(())
+        run_hooks(&self.after_build, crate_root, self.capture_output)?;
+        Ok(msis)
     }
 }
 
@@ -348,3 +521,20 @@ impl Default for Wix {
         Wix::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_from_triple_maps_known_architectures() {
+        assert_eq!(Platform::from_triple("x86_64-pc-windows-msvc").unwrap(), Platform::X64);
+        assert_eq!(Platform::from_triple("i686-pc-windows-msvc").unwrap(), Platform::X86);
+        assert_eq!(Platform::from_triple("aarch64-pc-windows-msvc").unwrap(), Platform::Arm64);
+    }
+
+    #[test]
+    fn platform_from_triple_rejects_unknown_triple() {
+        assert!(Platform::from_triple("sparc-unknown-linux-gnu").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for resolving package and binary information via `cargo metadata`
+//! instead of hand-parsing `Cargo.toml`. This works for both single-crate
+//! projects and workspaces, and for crates with more than one `[[bin]]`
+//! target.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use Error;
+
+const CARGO: &str = "cargo";
+
+/// The subset of `cargo metadata --format-version 1` that is needed to
+/// locate a package, its binaries, and the workspace's `target` directory.
+#[derive(Debug, Deserialize)]
+pub struct Metadata {
+    pub packages: Vec<Package>,
+    pub target_directory: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub targets: Vec<Target>,
+    pub manifest_path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    pub name: String,
+    pub kind: Vec<String>,
+}
+
+impl Target {
+    fn is_bin(&self) -> bool {
+        self.kind.iter().any(|k| k == "bin")
+    }
+}
+
+impl Metadata {
+    /// Invokes `cargo metadata` in the current directory and deserializes the
+    /// result.
+    pub fn from_cargo() -> Result<Self, Error> {
+        let output = Command::new(CARGO)
+            .arg("metadata")
+            .arg("--format-version")
+            .arg("1")
+            .arg("--no-deps")
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Metadata(format!(
+                "Failed to run 'cargo metadata' (exit code {}): {}",
+                output.status.code().map_or(String::from("unknown"), |c| c.to_string()),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        ::serde_json::from_slice(&output.stdout)
+            .map_err(|e| Error::Metadata(format!("Failed to parse 'cargo metadata' output: {}", e)))
+    }
+
+    /// Selects the package to build. If `name` is `None`, the metadata must
+    /// contain exactly one package, otherwise the caller must disambiguate
+    /// with `Wix::package`.
+    pub fn select_package(&self, name: Option<&str>) -> Result<&Package, Error> {
+        match name {
+            Some(name) => self.packages.iter().find(|p| p.name == name).ok_or_else(|| {
+                Error::Manifest(format!("No package named '{}' found in the workspace", name))
+            }),
+            None => match self.packages.len() {
+                1 => Ok(&self.packages[0]),
+                0 => Err(Error::Manifest(String::from("No packages found"))),
+                _ => Err(Error::Manifest(String::from(
+                    "Multiple packages found in the workspace; specify one with `package`",
+                ))),
+            },
+        }
+    }
+}
+
+impl Package {
+    /// Selects the binary target to package. If `name` is `None`, the
+    /// package must contain exactly one `[[bin]]` target, otherwise the
+    /// caller must disambiguate with `Wix::bin`.
+    pub fn select_bin(&self, name: Option<&str>) -> Result<&Target, Error> {
+        let mut bins = self.targets.iter().filter(|t| t.is_bin());
+        match name {
+            Some(name) => bins.find(|t| t.name == name).ok_or_else(|| {
+                Error::Manifest(format!("No binary target named '{}' found in package '{}'", name, self.name))
+            }),
+            None => {
+                let first = bins.next().ok_or_else(|| {
+                    Error::Manifest(format!("Package '{}' has no binary targets", self.name))
+                })?;
+                if bins.next().is_some() {
+                    return Err(Error::Manifest(format!(
+                        "Package '{}' has multiple binary targets; specify one with `bin`",
+                        self.name
+                    )));
+                }
+                Ok(first)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bin_target(name: &str) -> Target {
+        Target { name: name.to_owned(), kind: vec![String::from("bin")] }
+    }
+
+    fn package(name: &str, targets: Vec<Target>) -> Package {
+        Package {
+            name: name.to_owned(),
+            version: String::from("0.1.0"),
+            description: None,
+            authors: Vec::new(),
+            targets,
+            manifest_path: PathBuf::from(format!("{}/Cargo.toml", name)),
+        }
+    }
+
+    fn metadata(packages: Vec<Package>) -> Metadata {
+        Metadata { packages, target_directory: PathBuf::from("target") }
+    }
+
+    #[test]
+    fn select_package_defaults_to_the_only_package() {
+        let md = metadata(vec![package("foo", vec![bin_target("foo")])]);
+        assert_eq!(md.select_package(None).unwrap().name, "foo");
+    }
+
+    #[test]
+    fn select_package_errors_when_there_are_no_packages() {
+        let md = metadata(vec![]);
+        assert!(md.select_package(None).is_err());
+    }
+
+    #[test]
+    fn select_package_errors_when_ambiguous() {
+        let md = metadata(vec![
+            package("foo", vec![bin_target("foo")]),
+            package("bar", vec![bin_target("bar")]),
+        ]);
+        assert!(md.select_package(None).is_err());
+    }
+
+    #[test]
+    fn select_package_finds_named_package_in_a_workspace() {
+        let md = metadata(vec![
+            package("foo", vec![bin_target("foo")]),
+            package("bar", vec![bin_target("bar")]),
+        ]);
+        assert_eq!(md.select_package(Some("bar")).unwrap().name, "bar");
+    }
+
+    #[test]
+    fn select_package_errors_when_named_package_is_missing() {
+        let md = metadata(vec![package("foo", vec![bin_target("foo")])]);
+        assert!(md.select_package(Some("missing")).is_err());
+    }
+
+    #[test]
+    fn select_bin_defaults_to_the_only_binary() {
+        let pkg = package("foo", vec![bin_target("foo")]);
+        assert_eq!(pkg.select_bin(None).unwrap().name, "foo");
+    }
+
+    #[test]
+    fn select_bin_errors_when_there_are_no_binaries() {
+        let pkg = package("foo", vec![]);
+        assert!(pkg.select_bin(None).is_err());
+    }
+
+    #[test]
+    fn select_bin_errors_when_ambiguous() {
+        let pkg = package("foo", vec![bin_target("foo"), bin_target("foo2")]);
+        assert!(pkg.select_bin(None).is_err());
+    }
+
+    #[test]
+    fn select_bin_finds_named_binary() {
+        let pkg = package("foo", vec![bin_target("foo"), bin_target("foo2")]);
+        assert_eq!(pkg.select_bin(Some("foo2")).unwrap().name, "foo2");
+    }
+
+    #[test]
+    fn select_bin_errors_when_named_binary_is_missing() {
+        let pkg = package("foo", vec![bin_target("foo")]);
+        assert!(pkg.select_bin(Some("missing")).is_err());
+    }
+
+    #[test]
+    fn select_bin_ignores_non_bin_targets() {
+        let lib_target = Target { name: String::from("foo"), kind: vec![String::from("lib")] };
+        let pkg = package("foo", vec![lib_target, bin_target("foo_bin")]);
+        assert_eq!(pkg.select_bin(None).unwrap().name, "foo_bin");
+    }
+}
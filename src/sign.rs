@@ -0,0 +1,107 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configuration for the `signtool` signing step: timestamp server, file
+//! digest algorithm, and certificate selection.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The file digest algorithm passed to `signtool` via `/fd`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DigestAlgorithm {
+    Sha1,
+    #[default]
+    Sha256,
+}
+
+impl DigestAlgorithm {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            DigestAlgorithm::Sha1 => "sha1",
+            DigestAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+/// A timestamp server to embed in the signature, so it remains valid after
+/// the signing certificate expires.
+#[derive(Debug, Clone)]
+pub enum Timestamp {
+    /// A legacy Authenticode timestamp server URL, passed via `/t`.
+    Authenticode(String),
+    /// An RFC 3161 timestamp server URL, passed via `/tr` (with `/td` set to
+    /// the configured digest algorithm).
+    Rfc3161(String),
+}
+
+/// How to select the signing certificate.
+#[derive(Debug, Clone)]
+pub enum Certificate {
+    /// Select by SHA-1 thumbprint, passed via `/sha1`.
+    Thumbprint(String),
+    /// Select by subject name, passed via `/n`.
+    SubjectName(String),
+    /// A PFX file and optional password, passed via `/f` and `/p`.
+    Pfx(PathBuf, Option<String>),
+}
+
+/// Full `signtool` configuration. Defaults to `signtool sign /a /fd sha256`:
+/// automatic certificate selection, a SHA-256 file digest, and no timestamp.
+#[derive(Debug, Clone, Default)]
+pub struct SignConfig {
+    pub certificate: Option<Certificate>,
+    pub timestamp: Option<Timestamp>,
+    pub digest_algorithm: DigestAlgorithm,
+    pub description: Option<String>,
+}
+
+impl SignConfig {
+    /// Appends this configuration's arguments to a `signtool` command.
+    pub fn apply(&self, cmd: &mut Command) {
+        cmd.arg("sign");
+        match self.certificate {
+            Some(Certificate::Thumbprint(ref thumbprint)) => {
+                cmd.arg("/sha1").arg(thumbprint);
+            }
+            Some(Certificate::SubjectName(ref name)) => {
+                cmd.arg("/n").arg(name);
+            }
+            Some(Certificate::Pfx(ref file, ref password)) => {
+                cmd.arg("/f").arg(file);
+                if let Some(ref password) = *password {
+                    cmd.arg("/p").arg(password);
+                }
+            }
+            // Fall back to the previously hardcoded behavior of letting `signtool`
+            // automatically select the best certificate from the current user's store.
+            None => {
+                cmd.arg("/a");
+            }
+        }
+        cmd.arg("/fd").arg(self.digest_algorithm.as_str());
+        match self.timestamp {
+            Some(Timestamp::Authenticode(ref url)) => {
+                cmd.arg("/t").arg(url);
+            }
+            Some(Timestamp::Rfc3161(ref url)) => {
+                cmd.arg("/tr").arg(url).arg("/td").arg(self.digest_algorithm.as_str());
+            }
+            None => {}
+        }
+        if let Some(ref description) = self.description {
+            cmd.arg("/d").arg(description);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generates a `main.wxs` from a file manifest, so simple projects can build
+//! an MSI without hand-writing any WiX Source (WXS) XML. Each harvested file
+//! is assigned a component GUID that is deterministically derived from its
+//! install path, so the same file always gets the same GUID and upgrades do
+//! not disturb unrelated components.
+
+use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use Error;
+
+/// The namespace used to derive deterministic, name-based (v5) UUIDs for
+/// component GUIDs and upgrade codes. Any fixed UUID works here, as long as
+/// it never changes between releases of this crate.
+const GUID_NAMESPACE: &str = "0b47f0a6-3b1a-4d6a-9b6a-9e6f4f5c9a3e";
+
+/// Product-level information needed to generate a `main.wxs` from scratch.
+pub struct Manifest<'a> {
+    pub product_name: &'a str,
+    pub version: &'a str,
+    pub manufacturer: &'a str,
+    pub upgrade_code: String,
+}
+
+/// A single file to harvest into the installer.
+pub struct HarvestedFile {
+    /// The file's location on disk, to be embedded into the generated WXS as
+    /// the component's `Source` attribute.
+    pub source: PathBuf,
+    /// The file name as it should appear under `INSTALLFOLDER`.
+    pub install_name: String,
+}
+
+/// Expands `paths` into `HarvestedFile`s, recursing into any directory so
+/// every regular file it contains (at any depth) is harvested too. The
+/// generated WXS only models a single flat `INSTALLFOLDER`, so a file
+/// found inside a directory gets an `install_name` built from its path
+/// relative to that directory's parent, with path separators flattened to
+/// `_` (e.g. `assets/icons/logo.png` becomes `assets_icons_logo.png`).
+pub fn harvest(paths: &[PathBuf]) -> Result<Vec<HarvestedFile>, Error> {
+    let mut harvested = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            harvest_dir(path, path, &mut harvested)?;
+        } else {
+            let install_name = path.file_name()
+                .ok_or_else(|| Error::Generic(format!("'{}' has no file name", path.display())))?
+                .to_string_lossy()
+                .into_owned();
+            harvested.push(HarvestedFile { source: path.clone(), install_name });
+        }
+    }
+    Ok(harvested)
+}
+
+fn harvest_dir(root: &Path, dir: &Path, harvested: &mut Vec<HarvestedFile>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            harvest_dir(root, &entry_path, harvested)?;
+        } else {
+            let relative = entry_path.strip_prefix(root.parent().unwrap_or(root)).unwrap_or(&entry_path);
+            let install_name = relative.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("_");
+            harvested.push(HarvestedFile { source: entry_path, install_name });
+        }
+    }
+    Ok(())
+}
+
+fn namespace() -> Uuid {
+    Uuid::parse_str(GUID_NAMESPACE).expect("GUID_NAMESPACE is a valid UUID")
+}
+
+/// Deterministically derives an upgrade code for a product name. Because it
+/// only depends on the product name, it stays stable across versions, which
+/// is required for MSI upgrades to work.
+pub fn upgrade_code(product_name: &str) -> String {
+    Uuid::new_v5(&namespace(), product_name.as_bytes())
+        .to_hyphenated()
+        .to_string()
+        .to_uppercase()
+}
+
+/// Deterministically derives a component GUID from a file's install path.
+fn component_guid(install_name: &str) -> String {
+    Uuid::new_v5(&namespace(), install_name.as_bytes())
+        .to_hyphenated()
+        .to_string()
+        .to_uppercase()
+}
+
+fn component_id(install_name: &str) -> String {
+    format!("Component_{}", install_name.replace(|c: char| !c.is_alphanumeric(), "_"))
+}
+
+/// Escapes the characters that are not well-formed inside an XML attribute
+/// value. `product_name`/`manufacturer` come straight from `Cargo.toml`
+/// (and `manufacturer` is typically `"Name <email>"`, the default `authors`
+/// format, which is guaranteed to contain `<`/`>`), and harvested file names
+/// and paths are whatever is on disk, so none of it can be assumed to be
+/// XML-safe.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders a complete `main.wxs` document harvesting `files` into a single
+/// `ComponentGroup`/`Feature`. Fails if two files share an `install_name`,
+/// since that would produce duplicate `Component`/`File` `Id`s and GUIDs.
+pub fn generate(manifest: &Manifest, files: &[HarvestedFile]) -> Result<String, Error> {
+    let mut seen_install_names = HashSet::new();
+    for file in files {
+        if !seen_install_names.insert(file.install_name.as_str()) {
+            return Err(Error::Generic(format!(
+                "Two harvested files both install as '{}'; give them distinct file names",
+                file.install_name
+            )));
+        }
+    }
+    let product_name = xml_escape(manifest.product_name);
+    let manufacturer = xml_escape(manifest.manufacturer);
+    let mut wxs = String::new();
+    writeln!(wxs, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(wxs, r#"<Wix xmlns="http://schemas.microsoft.com/wix/2006/wi">"#).unwrap();
+    writeln!(wxs, r#"    <Product Id="*" Name="{name}" Language="1033" Version="{version}" Manufacturer="{manufacturer}" UpgradeCode="{upgrade_code}">"#,
+        name = product_name, version = manifest.version, manufacturer = manufacturer, upgrade_code = manifest.upgrade_code).unwrap();
+    writeln!(wxs, r#"        <Package InstallerVersion="450" Compressed="yes" InstallScope="perMachine" />"#).unwrap();
+    writeln!(wxs, r#"        <MajorUpgrade DowngradeErrorMessage="A newer version of [ProductName] is already installed." />"#).unwrap();
+    writeln!(wxs, r#"        <MediaTemplate EmbedCab="yes" />"#).unwrap();
+    writeln!(wxs).unwrap();
+    writeln!(wxs, r#"        <Directory Id="TARGETDIR" Name="SourceDir">"#).unwrap();
+    writeln!(wxs, r#"            <Directory Id="ProgramFilesFolder">"#).unwrap();
+    writeln!(wxs, r#"                <Directory Id="INSTALLFOLDER" Name="{name}" />"#, name = product_name).unwrap();
+    writeln!(wxs, r#"            </Directory>"#).unwrap();
+    writeln!(wxs, r#"        </Directory>"#).unwrap();
+    writeln!(wxs).unwrap();
+    writeln!(wxs, r#"        <ComponentGroup Id="ProductComponents" Directory="INSTALLFOLDER">"#).unwrap();
+    for file in files {
+        writeln!(wxs, r#"            <Component Id="{id}" Guid="{guid}">"#,
+            id = component_id(&file.install_name), guid = component_guid(&file.install_name)).unwrap();
+        writeln!(wxs, r#"                <File Id="{id}" Name="{name}" Source="{source}" KeyPath="yes" />"#,
+            id = component_id(&file.install_name), name = xml_escape(&file.install_name), source = xml_escape(&file.source.display().to_string())).unwrap();
+        writeln!(wxs, r#"            </Component>"#).unwrap();
+    }
+    writeln!(wxs, r#"        </ComponentGroup>"#).unwrap();
+    writeln!(wxs).unwrap();
+    writeln!(wxs, r#"        <Feature Id="MainFeature" Title="{name}" Level="1">"#, name = product_name).unwrap();
+    writeln!(wxs, r#"            <ComponentGroupRef Id="ProductComponents" />"#).unwrap();
+    writeln!(wxs, r#"        </Feature>"#).unwrap();
+    writeln!(wxs, r#"    </Product>"#).unwrap();
+    writeln!(wxs, r#"</Wix>"#).unwrap();
+    Ok(wxs)
+}
+
+/// Writes a generated `main.wxs` to `path`, unless a file already exists
+/// there, so advanced users who author their own template are left alone.
+pub fn write_if_missing(path: &Path, manifest: &Manifest, files: &[HarvestedFile]) -> Result<(), Error> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = generate(manifest, files)?;
+    let mut f = File::create(path)?;
+    f.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_guid_is_stable_for_a_given_install_name() {
+        assert_eq!(component_guid("app.exe"), component_guid("app.exe"));
+        assert_ne!(component_guid("app.exe"), component_guid("other.exe"));
+    }
+
+    #[test]
+    fn upgrade_code_is_stable_for_a_given_product_name() {
+        assert_eq!(upgrade_code("my-app"), upgrade_code("my-app"));
+        assert_ne!(upgrade_code("my-app"), upgrade_code("other-app"));
+    }
+
+    fn test_manifest<'a>(product_name: &'a str, manufacturer: &'a str) -> Manifest<'a> {
+        Manifest {
+            product_name,
+            version: "1.0.0",
+            manufacturer,
+            upgrade_code: upgrade_code(product_name),
+        }
+    }
+
+    #[test]
+    fn generate_rejects_duplicate_install_names() {
+        let manifest = test_manifest("Example", "Example Corp");
+        let files = vec![
+            HarvestedFile { source: PathBuf::from("assets/README.md"), install_name: "README.md".into() },
+            HarvestedFile { source: PathBuf::from("docs/README.md"), install_name: "README.md".into() },
+        ];
+        assert!(generate(&manifest, &files).is_err());
+    }
+
+    #[test]
+    fn generate_escapes_xml_special_characters() {
+        let manifest = test_manifest("Example", "Jane Doe <jane@example.com> & Co");
+        let wxs = generate(&manifest, &[]).unwrap();
+        assert!(!wxs.contains("Jane Doe <jane@example.com> & Co"));
+        assert!(wxs.contains("Jane Doe &lt;jane@example.com&gt; &amp; Co"));
+    }
+}